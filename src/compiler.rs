@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use chronlang_parser::{ast, parse};
 use chronlang_parser::ast::{Span, Spanned};
 use crate::language::Language;
@@ -15,11 +17,20 @@ pub enum CompilationError {
     ImportedNameNotFound(Span, String),
     ImportDependencyNotFound(String),
     NameCollision(Span, Symbol),
+    UnresolvedFeatures(Span, String),
+    ImportCycle(Span, Vec<String>),
+    /// An error raised while compiling an imported module, tagged with the
+    /// `source_name` its spans point into so diagnostics render against the
+    /// right file rather than the importing source.
+    InSource(String, Box<CompilationError>),
 }
 
 #[derive(Debug, PartialEq, Clone)]
 pub enum CompilationWarning {
     Unimplemented(Span, String),
+    /// A warning raised while compiling an imported module, tagged with its
+    /// owning `source_name`.
+    InSource(String, Box<CompilationWarning>),
 }
 
 #[derive(Debug, PartialEq)]
@@ -50,7 +61,16 @@ impl<'a> CompilerState<'a> {
     }
 }
 
-fn compile_ast(ast: Vec<(Span, ast::Stmt)>, source_name: &str, resolver: &impl Resolve) -> CompilationResult {
+/// State threaded through a whole import graph: the resolver, a cache of
+/// already-compiled sources keyed by `source_name`, and the stack of sources
+/// currently being compiled (the active import chain) used to detect cycles.
+struct ImportCtx<'a, R: Resolve> {
+    resolver: &'a R,
+    cache: HashMap<String, Project>,
+    stack: Vec<String>,
+}
+
+fn compile_ast<R: Resolve>(ast: Vec<(Span, ast::Stmt)>, source_name: &str, ctx: &mut ImportCtx<R>) -> CompilationResult {
     let mut project = Project::new();
     let mut state = CompilerState::new(source_name);
 
@@ -71,7 +91,7 @@ fn compile_ast(ast: Vec<(Span, ast::Stmt)>, source_name: &str, resolver: &impl R
             ast::Stmt::Trait { label, members } =>
                 compile_trait(&mut project, &mut state, &span, label, members),
             ast::Stmt::Import { path, names, .. } =>
-                compile_import(&mut project, &mut state, resolver, span, path, names),
+                compile_import(&mut project, &mut state, ctx, span, path, names),
         }
     }
 
@@ -107,14 +127,27 @@ fn compile_milestone(project: &mut Project, state: &mut CompilerState, span: &Sp
 fn compile_sound_change(project: &mut Project, state: &mut CompilerState, span: Span, source: Spanned<ast::Source>, target: Spanned<ast::Target>, environment: Option<Spanned<ast::Environment>>, description: Option<Spanned<String>>) {
     match &state.current_language {
         Some(lang) => {
-            project.sound_changes.push(SoundChange {
+            let change = SoundChange {
                 source_name: state.source_name.into(),
                 source,
                 target,
                 environment,
                 description,
                 tag: Tag::new(&lang.clone(), &state.current_time),
-            })
+            };
+
+            // Register the change as a symbol so it participates in the reverse
+            // `dependencies` index: `references_to(language)` then finds every
+            // sound change bound to that language, the same way it finds words.
+            let name = format!("{}#sound-change-{}", state.source_name, project.sound_changes.len());
+            let _ = project.add_symbol(Symbol {
+                name,
+                loc: Location { source_name: state.source_name.into(), span: change.source.0.clone() },
+                value: Entity::SoundChange(change.clone()),
+                dependencies: vec![lang.1.clone()],
+            });
+
+            project.sound_changes.push(change);
         }
         _ => state.errors.push(CompilationError::NoLanguage(span)),
     }
@@ -138,19 +171,20 @@ fn compile_language(project: &mut Project, state: &mut CompilerState, span: &Spa
 }
 
 fn compile_word(project: &mut Project, state: &mut CompilerState, span: &Span, gloss: Spanned<String>, pronunciation: Spanned<Vec<String>>, definitions: Vec<ast::Definition>) {
+    let form = project.normalization;
     match &state.current_language {
         Some(lang) => {
             let defs = definitions.iter()
                 .map(|def| { Definition::new(&def.pos, &def.definition) })
                 .collect::<Vec<Definition>>();
 
-            let word = Word::new(&gloss, &pronunciation, &defs, &Tag::new(lang, &state.current_time));
+            let word = Word::new(&gloss, &pronunciation, &defs, &Tag::new(lang, &state.current_time), form);
 
             let maybe_clash = project.add_symbol(Symbol {
                 name: gloss.1.clone(),
                 loc: Location { source_name: state.source_name.into(), span: span.clone() },
                 value: Entity::Word(word.clone()),
-                dependencies: vec![],
+                dependencies: vec![lang.1.clone()],
             });
 
             if let Err(clash) = maybe_clash {
@@ -164,7 +198,8 @@ fn compile_word(project: &mut Project, state: &mut CompilerState, span: &Span, g
 }
 
 fn compile_class(project: &mut Project, state: &mut CompilerState, span: &Span, label: Spanned<String>, encodes: Vec<Spanned<String>>, annotates: Vec<Spanned<String>>, phonemes: Vec<Spanned<ast::PhonemeDef>>) {
-    let phoneme_names = phonemes.iter().map(|(_, p)| p.label.1.clone()).collect::<Vec<_>>();
+    let form = project.normalization;
+    let phoneme_names = phonemes.iter().map(|(_, p)| form.apply(&p.label.1)).collect::<Vec<_>>();
     let encodes_names = encodes.iter().map(|(_, e)| e.clone()).collect::<Vec<_>>();
     let annotates_names = annotates.iter().map(|(_, e)| e.clone()).collect::<Vec<_>>();
 
@@ -187,9 +222,9 @@ fn compile_class(project: &mut Project, state: &mut CompilerState, span: &Span,
     let mut clashes = phonemes.iter()
         .flat_map(|(phoneme_span, phoneme)| {
             let symbol = Symbol {
-                name: phoneme.label.1.clone(),
+                name: form.apply(&phoneme.label.1),
                 loc: Location { source_name: state.source_name.into(), span: phoneme_span.clone() },
-                value: Entity::Phoneme { class: label.clone(), label: phoneme.label.clone(), traits: phoneme.traits.clone() },
+                value: Entity::Phoneme { class: label.clone(), label: (phoneme.label.0.clone(), form.apply(&phoneme.label.1)), traits: phoneme.traits.clone() },
                 dependencies: vec![label.1.clone()],
             };
 
@@ -263,26 +298,44 @@ fn compile_trait(project: &mut Project, state: &mut CompilerState, span: &Span,
     state.errors.append(&mut clashes);
 }
 
-fn compile_import<'a>(project: &mut Project, state: &'a mut CompilerState, resolver: &impl Resolve, span: Span, path: Vec<Spanned<String>>, names: Vec<Spanned<String>>) {
+fn compile_import<R: Resolve>(project: &mut Project, state: &mut CompilerState, ctx: &mut ImportCtx<R>, span: Span, path: Vec<Spanned<String>>, names: Vec<Spanned<String>>) {
     let seg_vec = path
         .iter()
         .map(|(_, seg)| seg.as_str())
         .collect::<Vec<_>>();
-    let path = &seg_vec[..];
-    let import_source = resolver.resolve(path);
+    let import_source = ctx.resolver.resolve(&seg_vec[..]);
     match import_source {
         Ok((source, import_source_name)) => {
-            let res = compile(source.as_str(), &import_source_name, resolver);
-            state.errors.append(&mut res.errors.clone());
-            state.warnings.append(&mut res.warnings.clone());
+            // A source already on the active chain is a cycle; record the path
+            // and bail rather than recursing until the stack overflows.
+            if let Some(pos) = ctx.stack.iter().position(|name| name == &import_source_name) {
+                let mut chain = ctx.stack[pos..].to_vec();
+                chain.push(import_source_name);
+                state.errors.push(CompilationError::ImportCycle(span, chain));
+                return;
+            }
+
+            // Compile each imported source at most once; its diagnostics are
+            // surfaced the first time and its project is memoized for reuse.
+            if !ctx.cache.contains_key(&import_source_name) {
+                ctx.stack.push(import_source_name.clone());
+                let mut res = compile_source(source.as_str(), &import_source_name, ctx);
+                ctx.stack.pop();
+                // Tag each imported diagnostic with its owning source so its
+                // caret renders against that module's text, not the importer's.
+                state.errors.extend(res.errors.drain(..).map(|e| CompilationError::InSource(import_source_name.clone(), Box::new(e))));
+                state.warnings.extend(res.warnings.drain(..).map(|w| CompilationWarning::InSource(import_source_name.clone(), Box::new(w))));
+                ctx.cache.insert(import_source_name.clone(), res.project);
+            }
 
+            let imported = &ctx.cache[&import_source_name];
             names
                 .into_iter()
                 .for_each(|(span, name)| {
                     let res = if name == "*" {
-                        project.import_all_from(&res.project)
+                        project.import_all_from(imported)
                     } else {
-                        project.import(&[name.as_str()], &res.project)
+                        project.import(&[name.as_str()], imported)
                     };
 
                     if let Err(errs) = res {
@@ -298,9 +351,9 @@ fn compile_import<'a>(project: &mut Project, state: &'a mut CompilerState, resol
     }
 }
 
-pub fn compile(source: &str, source_name: &str, resolver: &impl Resolve) -> CompilationResult {
+fn compile_source<R: Resolve>(source: &str, source_name: &str, ctx: &mut ImportCtx<R>) -> CompilationResult {
     match parse(source) {
-        Ok(ast) => compile_ast(ast, source_name, resolver),
+        Ok(ast) => compile_ast(ast, source_name, ctx),
         Err(errs) => CompilationResult {
             ok: false,
             project: Project::new(),
@@ -314,6 +367,15 @@ pub fn compile(source: &str, source_name: &str, resolver: &impl Resolve) -> Comp
     }
 }
 
+pub fn compile(source: &str, source_name: &str, resolver: &impl Resolve) -> CompilationResult {
+    let mut ctx = ImportCtx {
+        resolver,
+        cache: HashMap::new(),
+        stack: vec![source_name.to_string()],
+    };
+    compile_source(source, source_name, &mut ctx)
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::HashMap;
@@ -455,4 +517,26 @@ mod tests {
 
         assert_eq!(res.errors, expected_errors);
     }
+
+    #[test]
+    fn it_detects_mutual_import_cycles() {
+        let resolver = MockResolver::new(HashMap::from([
+            ("a".into(), "import * from b".into()),
+            ("b".into(), "import * from a".into()),
+        ]));
+
+        let res = compile("import * from a", "demo", &resolver);
+
+        // The cycle is detected inside an imported module, so it arrives wrapped
+        // in `InSource` tags recording the import chain it surfaced through.
+        fn is_cycle(error: &CompilationError) -> bool {
+            match error {
+                CompilationError::ImportCycle(_, _) => true,
+                CompilationError::InSource(_, inner) => is_cycle(inner),
+                _ => false,
+            }
+        }
+
+        assert!(res.errors.iter().any(is_cycle));
+    }
 }
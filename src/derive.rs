@@ -0,0 +1,333 @@
+use std::collections::HashSet;
+
+use chronlang_parser::ast;
+use chronlang_parser::ast::{Span, Spanned};
+
+use crate::compiler::CompilationError;
+use crate::project::{Entity, Project, SoundChange};
+use crate::tag::Tag;
+use crate::word::Word;
+
+/// A pronunciation snapshot taken after every sound change at a given milestone
+/// has been applied. Deriving a word yields one `Derivation` per intermediate
+/// milestone so callers can show the whole chronological reflex chain.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Derivation {
+    pub time: i64,
+    pub pronunciation: Vec<String>,
+}
+
+/// Evolve `word` along the ancestry of `target`'s language, applying every
+/// sound change tagged to a language in that chain whose milestone is no later
+/// than `target`. Returns the pronunciation after each intermediate milestone.
+pub fn derive(project: &Project, word: &Word, target: &Tag) -> Result<Vec<Derivation>, CompilationError> {
+    let lineage = lineage(project, &target.language);
+    let horizon = time_value(&target.time);
+
+    let mut changes = project
+        .sound_changes
+        .iter()
+        .filter(|sc| lineage.contains(&sc.tag.language) && time_value(&sc.tag.time) <= horizon)
+        .collect::<Vec<_>>();
+    changes.sort_by_key(|sc| time_value(&sc.tag.time));
+
+    apply_all(project, word.pronunciation.clone(), &changes)
+}
+
+pub(crate) fn apply_all(project: &Project, start: Vec<String>, changes: &[&SoundChange]) -> Result<Vec<Derivation>, CompilationError> {
+    let mut pronunciation = start;
+    let mut derivations = Vec::new();
+
+    let mut i = 0;
+    while i < changes.len() {
+        let time = time_value(&changes[i].tag.time);
+        while i < changes.len() && time_value(&changes[i].tag.time) == time {
+            pronunciation = apply_change(project, &pronunciation, changes[i])?;
+            i += 1;
+        }
+        derivations.push(Derivation { time, pronunciation: pronunciation.clone() });
+    }
+
+    Ok(derivations)
+}
+
+/// Apply a single sound change across `pronunciation`, rewriting every
+/// non-overlapping match left-to-right.
+fn apply_change(project: &Project, pronunciation: &[String], change: &SoundChange) -> Result<Vec<String>, CompilationError> {
+    let source = &change.source.1.patterns;
+    let target = &change.target.1.patterns;
+    let width = source.len();
+
+    let mut out: Vec<String> = Vec::with_capacity(pronunciation.len());
+    let mut i = 0;
+    while i < pronunciation.len() {
+        let window = &pronunciation[i..];
+        if width > 0 && window.len() >= width
+            && source_matches(project, source, &window[..width])
+            && environment_matches(project, &change.environment, pronunciation, i, width)
+        {
+            let rewritten = rewrite(project, &change.source.0, &window[..width], target)?;
+            out.extend(rewritten);
+            i += width;
+        } else {
+            out.push(pronunciation[i].clone());
+            i += 1;
+        }
+    }
+
+    Ok(out)
+}
+
+fn source_matches(project: &Project, patterns: &[Spanned<ast::Pattern>], segments: &[String]) -> bool {
+    patterns
+        .iter()
+        .zip(segments)
+        .all(|((_, pattern), segment)| matches_pattern(project, pattern, Some(segment)))
+}
+
+/// Check the environment of a match. `_` marks the focus slot; patterns before
+/// it are matched against the segments ending just before the match, patterns
+/// after it against the segments that follow. A `#` boundary pattern matches a
+/// word edge.
+fn environment_matches(project: &Project, environment: &Option<Spanned<ast::Environment>>, pronunciation: &[String], start: usize, width: usize) -> bool {
+    let Some((_, environment)) = environment else { return true };
+
+    let before = &environment.before;
+    for (offset, (_, pattern)) in before.iter().rev().enumerate() {
+        let segment = start.checked_sub(offset + 1).map(|idx| pronunciation[idx].as_str());
+        if !matches_pattern(project, pattern, segment) {
+            return false;
+        }
+    }
+
+    let after = &environment.after;
+    for (offset, (_, pattern)) in after.iter().enumerate() {
+        let idx = start + width + offset;
+        let segment = pronunciation.get(idx).map(|s| s.as_str());
+        if !matches_pattern(project, pattern, segment) {
+            return false;
+        }
+    }
+
+    true
+}
+
+fn matches_pattern(project: &Project, pattern: &ast::Pattern, segment: Option<&str>) -> bool {
+    match pattern {
+        ast::Pattern::Boundary => segment.is_none(),
+        // Rule literals are normalized to the project's form here because, unlike
+        // word segments and phoneme labels, they are stored raw from the parser.
+        ast::Pattern::Literal(phoneme) => {
+            let phoneme = project.normalization.apply(phoneme);
+            segment == Some(phoneme.as_str())
+        }
+        ast::Pattern::Matrix { base, modifiers } => match segment.and_then(|s| phoneme_features(project, s)) {
+            Some((class, traits)) => {
+                base.as_ref().map(|(_, b)| project.normalization.apply(b) == class).unwrap_or(true)
+                    && modifiers.iter().all(|modifier| match modifier.sign {
+                        ast::Sign::Plus => traits.contains(&modifier.label.1),
+                        ast::Sign::Minus => !traits.contains(&modifier.label.1),
+                    })
+            }
+            None => false,
+        },
+    }
+}
+
+/// Produce the replacement segments for one match. A target that is a single
+/// feature bundle with no base class (e.g. `[+flap]`) rewrites each matched
+/// phoneme's trait bundle and re-resolves it to a concrete phoneme in its class.
+/// Any other target replaces the matched span with its literal phonemes,
+/// normalized to the project's form; its non-literal elements (including
+/// feature bundles with a base class) contribute no segments.
+fn rewrite(project: &Project, span: &Span, matched: &[String], target: &[Spanned<ast::Pattern>]) -> Result<Vec<String>, CompilationError> {
+    match target {
+        [(_, ast::Pattern::Matrix { base: None, modifiers })] => matched
+            .iter()
+            .map(|segment| resolve_features(project, span, segment, modifiers))
+            .collect(),
+        _ => Ok(target
+            .iter()
+            .filter_map(|(_, pattern)| match pattern {
+                ast::Pattern::Literal(phoneme) => Some(project.normalization.apply(phoneme)),
+                _ => None,
+            })
+            .collect()),
+    }
+}
+
+fn resolve_features(project: &Project, span: &Span, segment: &str, modifiers: &[ast::Modifier]) -> Result<String, CompilationError> {
+    let Some((class, mut traits)) = phoneme_features(project, segment) else {
+        return Err(CompilationError::UnresolvedFeatures(span.clone(), segment.to_string()));
+    };
+
+    for modifier in modifiers {
+        match modifier.sign {
+            ast::Sign::Plus => {
+                // Overriding a feature drops the phoneme's existing member of the
+                // same trait dimension before adding the new one, so e.g. `+flap`
+                // replaces `stop` instead of stacking beside it.
+                for sibling in sibling_members(project, &modifier.label.1) {
+                    traits.remove(&sibling);
+                }
+                traits.insert(modifier.label.1.clone());
+            }
+            ast::Sign::Minus => { traits.remove(&modifier.label.1); }
+        }
+    }
+
+    // Resolve the rewritten bundle back to a concrete phoneme in the class,
+    // breaking ties by label then definition order so the reflex never depends
+    // on hash-map iteration order.
+    let mut candidates = project
+        .symbols
+        .values()
+        .filter_map(|symbol| match &symbol.value {
+            Entity::Phoneme { class: (_, phoneme_class), label, traits: phoneme_traits }
+                if phoneme_class == &class
+                    && phoneme_traits.iter().map(|(_, t)| t.clone()).collect::<HashSet<_>>() == traits =>
+            {
+                Some((label.1.clone(), symbol.loc.span.start))
+            }
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    candidates.sort();
+
+    candidates
+        .into_iter()
+        .next()
+        .map(|(label, _)| label)
+        .ok_or_else(|| CompilationError::UnresolvedFeatures(span.clone(), segment.to_string()))
+}
+
+/// Every trait member sharing a dimension with `member` — the values it is
+/// mutually exclusive with (e.g. `stop`, `flap`, `approximant` for a manner
+/// trait) — so a feature override can clear the old member before setting the
+/// new one.
+fn sibling_members(project: &Project, member: &str) -> Vec<String> {
+    let Some(dimension) = trait_of(project, member) else { return Vec::new() };
+    project
+        .symbols
+        .values()
+        .filter_map(|symbol| match &symbol.value {
+            Entity::TraitMember { label, .. } if symbol.dependencies.first() == Some(&dimension) =>
+                Some(label.1.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// The trait (dimension) a member belongs to, matched by its primary label or
+/// one of its aliases.
+fn trait_of(project: &Project, member: &str) -> Option<String> {
+    project.symbols.values().find_map(|symbol| match &symbol.value {
+        Entity::TraitMember { label, aliases, .. }
+            if label.1 == member || aliases.iter().any(|(_, alias)| alias == member) =>
+            symbol.dependencies.first().cloned(),
+        _ => None,
+    })
+}
+
+/// Resolve a pronunciation segment to its `(class, traits)` via the phoneme
+/// symbols in the project, returning `None` when the segment is not a known
+/// phoneme.
+fn phoneme_features(project: &Project, segment: &str) -> Option<(String, HashSet<String>)> {
+    match project.symbols.get(segment).map(|symbol| &symbol.value) {
+        Some(Entity::Phoneme { class, traits, .. }) => Some((
+            class.1.clone(),
+            traits.iter().map(|(_, t)| t.clone()).collect(),
+        )),
+        _ => None,
+    }
+}
+
+/// Apply `changes` to `start` in order, threading the intermediate
+/// pronunciation through each rule and returning the final form. A rule that
+/// fails to resolve is skipped, leaving the pronunciation unchanged.
+pub(crate) fn apply_sequence(project: &Project, start: Vec<String>, changes: &[&SoundChange]) -> Vec<String> {
+    changes.iter().fold(start, |pronunciation, change| {
+        match apply_change(project, &pronunciation, change) {
+            Ok(next) => next,
+            Err(_) => pronunciation,
+        }
+    })
+}
+
+/// The chronological chain of language ids from `language` up through its
+/// ancestors, used to decide which sound changes apply to a word.
+pub(crate) fn lineage(project: &Project, language: &str) -> HashSet<String> {
+    let mut chain = HashSet::new();
+    let mut current = Some(language.to_string());
+
+    while let Some(id) = current {
+        if !chain.insert(id.clone()) {
+            break;
+        }
+        current = project
+            .languages
+            .iter()
+            .find(|lang| lang.id() == id)
+            .and_then(|lang| lang.parent().map(|p| p.to_string()));
+    }
+
+    chain
+}
+
+pub(crate) fn time_value(time: &ast::Time) -> i64 {
+    match time {
+        ast::Time::Instant(t) => *t,
+        ast::Time::Range(_, t) => *t,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use crate::compiler::compile;
+    use crate::resolver::MockResolver;
+
+    use super::*;
+
+    #[test]
+    fn it_lenites_alveolar_stops_intervocalically() {
+        let resolver = MockResolver::new(HashMap::new());
+
+        let res = compile(
+            "
+            trait Manner { stop, flap, approximant }
+            trait Place { alveolar }
+
+            class C encodes (Place Manner) {
+                a = alveolar approximant,
+                t = alveolar stop,
+                ɾ = alveolar flap,
+            }
+
+            lang OEng : Old English
+            lang AmEng < OEng : American English
+
+            @ 1000, OEng
+            - ata /a.t.a/ { noun. a test word }
+
+            @ 1940, AmEng
+            $ [C+alveolar+stop] > [+flap] / [C+approximant]_[C+approximant]
+            ",
+            "demo",
+            &resolver,
+        );
+
+        assert!(res.ok);
+
+        let word = res.project.words.iter().find(|w| w.gloss == "ata").unwrap();
+        let target = res.project.tags.last().unwrap();
+
+        let derivations = derive(&res.project, word, target).unwrap();
+
+        assert_eq!(
+            derivations.last().map(|d| d.pronunciation.clone()),
+            Some(vec!["a".into(), "ɾ".into(), "a".into()]),
+        );
+    }
+}
@@ -0,0 +1,252 @@
+use std::collections::HashMap;
+
+use chronlang_parser::ast::Span;
+
+use crate::compiler::{CompilationError, CompilationWarning};
+
+/// The set of source texts seen during a compilation, keyed by the
+/// `source_name` the resolver returned. Imports pull in more than one source,
+/// so diagnostics are rendered against this collection rather than a single
+/// string.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Sources {
+    texts: HashMap<String, String>,
+    entry: Option<String>,
+}
+
+impl Sources {
+    pub fn new() -> Self {
+        Self { texts: HashMap::new(), entry: None }
+    }
+
+    pub fn insert(&mut self, source_name: &str, text: &str) {
+        self.entry.get_or_insert_with(|| source_name.to_string());
+        self.texts.insert(source_name.to_string(), text.to_string());
+    }
+
+    pub fn get(&self, source_name: &str) -> Option<&str> {
+        self.texts.get(source_name).map(|s| s.as_str())
+    }
+
+    /// The first source inserted — the entry point a top-level diagnostic's
+    /// primary span points into.
+    pub fn entry_name(&self) -> Option<&str> {
+        self.entry.as_deref()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// A single span-anchored annotation. A diagnostic has one primary label and
+/// any number of secondary ones (e.g. the original symbol behind a name
+/// collision).
+#[derive(Clone, Debug, PartialEq)]
+pub struct Label {
+    pub source_name: String,
+    pub span: Span,
+    pub message: String,
+}
+
+/// A renderable report built from a `CompilationError` or `CompilationWarning`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub message: String,
+    pub primary: Label,
+    pub secondary: Vec<Label>,
+}
+
+impl Diagnostic {
+    /// Render the diagnostic as plain text with source snippets and carets.
+    pub fn render(&self, sources: &Sources) -> String {
+        self.render_with(sources, false)
+    }
+
+    /// Render the diagnostic with ANSI colour escapes for terminals.
+    pub fn render_ansi(&self, sources: &Sources) -> String {
+        self.render_with(sources, true)
+    }
+
+    fn render_with(&self, sources: &Sources, color: bool) -> String {
+        let (heading, accent) = match self.severity {
+            Severity::Error => ("error", RED),
+            Severity::Warning => ("warning", YELLOW),
+        };
+
+        let mut out = String::new();
+        out.push_str(&paint(color, accent, heading));
+        out.push_str(&paint(color, BOLD, &format!(": {}", self.message)));
+        out.push('\n');
+
+        out.push_str(&render_label(&self.primary, sources, color, accent));
+        for label in &self.secondary {
+            out.push_str(&render_label(label, sources, color, BLUE));
+        }
+
+        out
+    }
+}
+
+fn render_label(label: &Label, sources: &Sources, color: bool, accent: &str) -> String {
+    let Some(source) = sources.get(&label.source_name) else {
+        return format!("  --> {}\n", label.source_name);
+    };
+
+    let (line, column) = line_column(source, label.span.start);
+    let text = source.lines().nth(line - 1).unwrap_or("");
+    let gutter = " ".repeat(line.to_string().len());
+
+    let width = label
+        .span
+        .end
+        .saturating_sub(label.span.start)
+        .max(1)
+        .min(text.len().saturating_sub(column - 1).max(1));
+
+    let caret = format!("{}{}", " ".repeat(column - 1), "^".repeat(width));
+    let caret = if label.message.is_empty() {
+        caret
+    } else {
+        format!("{caret} {}", label.message)
+    };
+
+    format!(
+        "{gutter} --> {}:{line}:{column}\n{gutter} |\n{line} | {text}\n{gutter} | {}\n",
+        label.source_name,
+        paint(color, accent, &caret),
+    )
+}
+
+/// Compute the 1-based line and column of a byte offset, counting columns in
+/// Unicode scalar values so multi-byte IPA segments line up under the caret.
+fn line_column(source: &str, offset: usize) -> (usize, usize) {
+    let offset = offset.min(source.len());
+    let mut line = 1;
+    let mut line_start = 0;
+    for (idx, byte) in source.bytes().enumerate().take(offset) {
+        if byte == b'\n' {
+            line += 1;
+            line_start = idx + 1;
+        }
+    }
+    let column = source[line_start..offset].chars().count() + 1;
+    (line, column)
+}
+
+const RED: &str = "\u{1b}[31m";
+const YELLOW: &str = "\u{1b}[33m";
+const BLUE: &str = "\u{1b}[34m";
+const BOLD: &str = "\u{1b}[1m";
+const RESET: &str = "\u{1b}[0m";
+
+fn paint(color: bool, code: &str, text: &str) -> String {
+    if color {
+        format!("{code}{text}{RESET}")
+    } else {
+        text.to_string()
+    }
+}
+
+impl CompilationError {
+    /// Build a renderable diagnostic. `source_name` names the source the primary
+    /// span points into (the one currently being compiled).
+    pub fn to_diagnostic(&self, source_name: &str) -> Diagnostic {
+        let error = |span: &Span, message: String| Diagnostic {
+            severity: Severity::Error,
+            message,
+            primary: Label { source_name: source_name.to_string(), span: span.clone(), message: String::new() },
+            secondary: Vec::new(),
+        };
+
+        match self {
+            CompilationError::ParseErrors(errors) => Diagnostic {
+                severity: Severity::Error,
+                message: "failed to parse source".into(),
+                primary: errors
+                    .first()
+                    .map(|(span, message)| Label { source_name: source_name.to_string(), span: span.clone(), message: message.clone() })
+                    .unwrap_or(Label { source_name: source_name.to_string(), span: 0..0, message: String::new() }),
+                secondary: errors
+                    .iter()
+                    .skip(1)
+                    .map(|(span, message)| Label { source_name: source_name.to_string(), span: span.clone(), message: message.clone() })
+                    .collect(),
+            },
+            CompilationError::FirstTagNoLanguage(span) =>
+                error(span, "the first milestone must name a language".into()),
+            CompilationError::NoLanguage(span) =>
+                error(span, "no language is in scope; open one with a milestone first".into()),
+            CompilationError::BadImport(span, reason) =>
+                error(span, format!("could not import: {reason}")),
+            CompilationError::ImportedNameNotFound(span, name) =>
+                error(span, format!("`{name}` is not exported by the imported module")),
+            CompilationError::ImportDependencyNotFound(name) => Diagnostic {
+                severity: Severity::Error,
+                message: format!("imported symbol depends on `{name}`, which could not be found"),
+                primary: Label { source_name: source_name.to_string(), span: 0..0, message: String::new() },
+                secondary: Vec::new(),
+            },
+            CompilationError::NameCollision(span, original) => Diagnostic {
+                severity: Severity::Error,
+                message: format!("the name `{}` is already defined", original.name),
+                primary: Label { source_name: source_name.to_string(), span: span.clone(), message: "redefined here".into() },
+                secondary: vec![Label {
+                    source_name: original.loc.source_name.clone(),
+                    span: original.loc.span.clone(),
+                    message: "first defined here".into(),
+                }],
+            },
+            CompilationError::UnresolvedFeatures(span, segment) =>
+                error(span, format!("the feature bundle applied to `{segment}` does not resolve to any phoneme in its class")),
+            CompilationError::ImportCycle(span, chain) =>
+                error(span, format!("import cycle detected: {}", chain.join(" -> "))),
+            // Render against the imported module's own source, overriding the
+            // importing source threaded in by the caller.
+            CompilationError::InSource(source_name, inner) => inner.to_diagnostic(source_name),
+        }
+    }
+}
+
+impl CompilationWarning {
+    pub fn to_diagnostic(&self, source_name: &str) -> Diagnostic {
+        match self {
+            CompilationWarning::Unimplemented(span, feature) => Diagnostic {
+                severity: Severity::Warning,
+                message: format!("`{feature}` is not yet implemented and was ignored"),
+                primary: Label { source_name: source_name.to_string(), span: span.clone(), message: String::new() },
+                secondary: Vec::new(),
+            },
+            CompilationWarning::InSource(source_name, inner) => inner.to_diagnostic(source_name),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_renders_a_caret_under_the_span() {
+        let mut sources = Sources::new();
+        sources.insert("demo", "lang A\nlang A");
+
+        let diagnostic = Diagnostic {
+            severity: Severity::Error,
+            message: "the name `A` is already defined".into(),
+            primary: Label { source_name: "demo".into(), span: 12..13, message: "redefined here".into() },
+            secondary: vec![Label { source_name: "demo".into(), span: 5..6, message: "first defined here".into() }],
+        };
+
+        let rendered = diagnostic.render(&sources);
+
+        assert!(rendered.contains("error: the name `A` is already defined"));
+        assert!(rendered.contains("demo:2:6"));
+        assert!(rendered.contains("^ redefined here"));
+        assert!(rendered.contains("demo:1:6"));
+        assert!(rendered.contains("^ first defined here"));
+    }
+}
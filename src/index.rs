@@ -0,0 +1,154 @@
+use std::collections::BTreeMap;
+
+use fst::automaton::{Str, Subsequence};
+use fst::{Automaton, IntoStreamer, Map, MapBuilder, Streamer};
+
+use crate::word::Word;
+
+/// The delimiter bracketing every segment in an FST key. It never occurs inside
+/// an IPA segment, so surrounding each segment with it lets a byte-level
+/// automaton align a query only at whole-segment boundaries.
+const SEP: char = '\u{1f}';
+
+/// A finite-state-transducer index over word pronunciations. Each word's
+/// segment list is concatenated into a key mapped to the indices of the words
+/// that share it, supporting compact exact, prefix and subsequence lookups.
+pub struct PronunciationIndex {
+    map: Map<Vec<u8>>,
+    postings: Vec<Vec<usize>>,
+}
+
+impl PronunciationIndex {
+    /// Build the index from a slice of words, keyed by position in that slice.
+    pub fn build(words: &[Word]) -> Self {
+        let mut grouped: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+        for (idx, word) in words.iter().enumerate() {
+            grouped.entry(key(&word.pronunciation)).or_default().push(idx);
+        }
+
+        let mut builder = MapBuilder::memory();
+        let mut postings = Vec::with_capacity(grouped.len());
+        for (key, indices) in grouped {
+            // MapBuilder requires strictly increasing keys; BTreeMap gives them
+            // to us in order, and the u64 value is an offset into `postings`.
+            builder.insert(key, postings.len() as u64).expect("keys are sorted and unique");
+            postings.push(indices);
+        }
+
+        let bytes = builder.into_inner().expect("in-memory builder cannot fail");
+        let map = Map::new(bytes).expect("builder produced a valid fst");
+        Self { map, postings }
+    }
+
+    /// Word indices whose pronunciation is exactly `pattern`.
+    pub fn exact(&self, pattern: &[&str]) -> Vec<usize> {
+        self.map
+            .get(key_from_refs(pattern))
+            .map(|value| self.postings[value as usize].clone())
+            .unwrap_or_default()
+    }
+
+    /// Word indices whose pronunciation starts with `pattern`.
+    pub fn starts_with(&self, pattern: &[&str]) -> Vec<usize> {
+        self.collect(Str::new(&key_from_refs(pattern)).starts_with())
+    }
+
+    /// Word indices whose pronunciation contains `pattern` as a segment
+    /// subsequence, e.g. every word with a /k/ somewhere before a /t/.
+    pub fn contains(&self, pattern: &[&str]) -> Vec<usize> {
+        // The subsequence automaton matches on bytes, so it can still align a
+        // query char inside a multi-codepoint segment (e.g. `t` within `t͡s`).
+        // Use it only to prune candidates, then confirm each key is a genuine
+        // whole-segment subsequence before accepting its postings.
+        let mut out = Vec::new();
+        let mut stream = self.map.search(Subsequence::new(&key_from_refs(pattern))).into_stream();
+        while let Some((key, value)) = stream.next() {
+            let segments = segments_of(std::str::from_utf8(key).expect("keys are valid utf-8"));
+            if is_subsequence(&segments, pattern) {
+                out.extend(self.postings[value as usize].iter().copied());
+            }
+        }
+        out.sort_unstable();
+        out
+    }
+
+    fn collect(&self, automaton: impl Automaton) -> Vec<usize> {
+        let mut out = Vec::new();
+        let mut stream = self.map.search(automaton).into_stream();
+        while let Some((_, value)) = stream.next() {
+            out.extend(self.postings[value as usize].iter().copied());
+        }
+        out.sort_unstable();
+        out
+    }
+}
+
+fn key(segments: &[String]) -> String {
+    bracket(segments.iter().map(|segment| segment.as_str()))
+}
+
+fn key_from_refs(segments: &[&str]) -> String {
+    bracket(segments.iter().copied())
+}
+
+/// Build a key by bracketing every segment with `SEP`, including the word
+/// edges, so matches can only align at segment boundaries.
+fn bracket<'a>(segments: impl Iterator<Item = &'a str>) -> String {
+    let mut key = String::from(SEP);
+    for segment in segments {
+        key.push_str(segment);
+        key.push(SEP);
+    }
+    key
+}
+
+/// Split a bracketed key back into its segments.
+fn segments_of(key: &str) -> Vec<&str> {
+    key.split(SEP).filter(|segment| !segment.is_empty()).collect()
+}
+
+/// Whether `pattern` occurs in `segments` as a subsequence of whole segments.
+fn is_subsequence(segments: &[&str], pattern: &[&str]) -> bool {
+    let mut segments = segments.iter();
+    pattern.iter().all(|needle| segments.any(|segment| segment == needle))
+}
+
+#[cfg(test)]
+mod tests {
+    use chronlang_parser::ast;
+
+    use crate::normalize::Form;
+    use crate::tag::Tag;
+
+    use super::*;
+
+    fn word(gloss: &str, pronunciation: &[&str]) -> Word {
+        let prn = pronunciation.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        let tag = Tag::new(&(0..0, "Lang".into()), &(0..0, ast::Time::Instant(0)));
+        Word::new(&(0..0, gloss.into()), &(0..0, prn), &Vec::new(), &tag, Form::Nfd)
+    }
+
+    #[test]
+    fn contains_finds_a_subsequence() {
+        let words = vec![word("act", &["a", "k", "t"]), word("tea", &["t", "e", "a"])];
+        let index = PronunciationIndex::build(&words);
+
+        assert_eq!(index.contains(&["k", "t"]), vec![0]);
+        assert_eq!(index.exact(&["t", "e", "a"]), vec![1]);
+        assert_eq!(index.starts_with(&["a"]), vec![0]);
+    }
+
+    #[test]
+    fn matches_respect_segment_boundaries() {
+        // `t͡s` is a single affricate segment whose char string contains `t`.
+        let words = vec![word("cats", &["k", "a", "t͡s"]), word("at", &["a", "t"])];
+        let index = PronunciationIndex::build(&words);
+
+        // A `t` query must not match the affricate, only the real /t/ segment.
+        assert_eq!(index.contains(&["t"]), vec![1]);
+        assert_eq!(index.contains(&["t͡s"]), vec![0]);
+        // Prefix matching must not align `a` against the start of a longer
+        // segment either.
+        assert_eq!(index.starts_with(&["a"]), vec![1]);
+    }
+}
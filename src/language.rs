@@ -1,6 +1,7 @@
 use chronlang_parser::ast::{Span, Spanned};
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Language {
     id: String,
     id_span: Span,
@@ -19,4 +20,16 @@ impl Language {
             parent_span: parent.clone().map(|p| p.0.clone())
         }
     }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn parent(&self) -> Option<&str> {
+        self.parent.as_deref()
+    }
 }
\ No newline at end of file
@@ -0,0 +1,13 @@
+pub mod compiler;
+pub mod derive;
+pub mod diagnostics;
+pub mod index;
+pub mod language;
+pub mod lsp;
+pub mod normalize;
+pub mod project;
+pub mod resolver;
+pub mod search;
+pub mod syllable;
+pub mod tag;
+pub mod word;
@@ -0,0 +1,140 @@
+use std::collections::HashMap;
+
+use crate::compiler::{compile, CompilationResult};
+use crate::diagnostics::{Diagnostic, Severity};
+use crate::project::{Entity, Location, Symbol};
+use crate::resolver::Resolve;
+
+/// A zero-based line/character position, the coordinate space the language
+/// server protocol speaks in. Byte spans are converted to this for editors.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Position {
+    pub line: usize,
+    pub character: usize,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Range {
+    pub start: Position,
+    pub end: Position,
+}
+
+/// A diagnostic mapped into LSP coordinates, ready to publish for a document.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PublishedDiagnostic {
+    pub range: Range,
+    pub severity: Severity,
+    pub message: String,
+}
+
+/// One open document: its latest text and the project compiled from it.
+struct Document {
+    text: String,
+    result: CompilationResult,
+}
+
+/// Keeps a compiled `Project` per open document and recompiles on change,
+/// exposing the language-server queries the editor needs: diagnostics,
+/// definition, hover and references.
+pub struct Workspace<R: Resolve> {
+    resolver: R,
+    documents: HashMap<String, Document>,
+}
+
+impl<R: Resolve> Workspace<R> {
+    pub fn new(resolver: R) -> Self {
+        Self { resolver, documents: HashMap::new() }
+    }
+
+    /// Open or replace a document, compiling it immediately. `didOpen` and
+    /// `didChange` both funnel here since the engine recompiles from scratch.
+    pub fn open(&mut self, uri: &str, text: &str) {
+        let result = compile(text, uri, &self.resolver);
+        self.documents.insert(uri.to_string(), Document { text: text.to_string(), result });
+    }
+
+    /// Diagnostics for `uri` in LSP coordinates — `textDocument/publishDiagnostics`.
+    pub fn diagnostics(&self, uri: &str) -> Vec<PublishedDiagnostic> {
+        let Some(document) = self.documents.get(uri) else { return Vec::new() };
+
+        let to_published = |diagnostic: Diagnostic| PublishedDiagnostic {
+            range: self.span_range(uri, &diagnostic.primary.span),
+            severity: diagnostic.severity,
+            message: diagnostic.message,
+        };
+
+        document
+            .result
+            .warnings
+            .iter()
+            .map(|warning| to_published(warning.to_diagnostic(uri)))
+            .chain(document.result.errors.iter().map(|error| to_published(error.to_diagnostic(uri))))
+            .collect()
+    }
+
+    /// The definition location of the symbol under the cursor — `textDocument/definition`.
+    pub fn definition(&self, uri: &str, offset: usize) -> Option<Location> {
+        self.symbol_at(uri, offset).map(|symbol| symbol.loc.clone())
+    }
+
+    /// A short description of the symbol under the cursor — `textDocument/hover`.
+    pub fn hover(&self, uri: &str, offset: usize) -> Option<String> {
+        self.symbol_at(uri, offset).map(describe)
+    }
+
+    /// Every definition that depends on the symbol under the cursor —
+    /// `textDocument/references`.
+    pub fn references(&self, uri: &str, offset: usize) -> Vec<Location> {
+        let Some(document) = self.documents.get(uri) else { return Vec::new() };
+        let Some(symbol) = self.symbol_at(uri, offset) else { return Vec::new() };
+
+        document
+            .result
+            .project
+            .references_to(&symbol.name)
+            .into_iter()
+            .map(|symbol| symbol.loc.clone())
+            .collect()
+    }
+
+    fn symbol_at(&self, uri: &str, offset: usize) -> Option<&Symbol> {
+        self.documents.get(uri).and_then(|document| document.result.project.symbol_at(offset))
+    }
+
+    fn span_range(&self, uri: &str, span: &chronlang_parser::ast::Span) -> Range {
+        let text = self.documents.get(uri).map(|document| document.text.as_str()).unwrap_or("");
+        Range {
+            start: offset_to_position(text, span.start),
+            end: offset_to_position(text, span.end),
+        }
+    }
+}
+
+/// A one-line description of a symbol for hover, keyed off its entity kind.
+fn describe(symbol: &Symbol) -> String {
+    match &symbol.value {
+        Entity::Language(language) => format!("language `{}` ({})", symbol.name, language.name()),
+        Entity::Word(word) => format!("word `{}` /{}/", symbol.name, word.pronunciation.join("")),
+        Entity::Class { .. } => format!("class `{}`", symbol.name),
+        Entity::Phoneme { class, .. } => format!("phoneme `{}` of class `{}`", symbol.name, class.1),
+        Entity::Series { .. } => format!("series `{}`", symbol.name),
+        Entity::Trait { .. } => format!("trait `{}`", symbol.name),
+        Entity::TraitMember { .. } => format!("trait member `{}`", symbol.name),
+        Entity::SoundChange(change) => format!("sound change ({})", change.tag.language),
+    }
+}
+
+/// Convert a byte offset into a zero-based line/character position, counting
+/// characters in Unicode scalar values so multi-byte IPA lines up.
+fn offset_to_position(text: &str, offset: usize) -> Position {
+    let offset = offset.min(text.len());
+    let mut line = 0;
+    let mut line_start = 0;
+    for (idx, byte) in text.bytes().enumerate().take(offset) {
+        if byte == b'\n' {
+            line += 1;
+            line_start = idx + 1;
+        }
+    }
+    Position { line, character: text[line_start..offset].chars().count() }
+}
@@ -0,0 +1,202 @@
+use std::fs;
+use std::io::{IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use clap::{Parser, Subcommand, ValueEnum};
+
+use chronlang_engine::compiler::{compile, CompilationResult};
+use chronlang_engine::diagnostics::Sources;
+use chronlang_engine::resolver::{
+    ChainResolver, FileSystemResolver, HttpFetch, Lockfile, RegistryResolver, Resolve,
+};
+
+/// Compile chronlang sources from the shell or CI.
+#[derive(Parser)]
+#[command(name = "chronlang", about = "A compiler for the chronlang conlang description language")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Compile a source and report any diagnostics.
+    Check(Check),
+    /// Compile a source and serialize the resulting project.
+    Build(Build),
+}
+
+#[derive(Parser)]
+struct Check {
+    /// The entry-point source file to compile.
+    file: PathBuf,
+    #[command(flatten)]
+    common: Common,
+}
+
+#[derive(Parser)]
+struct Build {
+    /// The entry-point source file to compile.
+    file: PathBuf,
+    /// Write the serialized project here instead of stdout.
+    #[arg(short, long)]
+    out: Option<PathBuf>,
+    #[command(flatten)]
+    common: Common,
+}
+
+#[derive(Parser)]
+struct Common {
+    /// Which resolver to use for imports.
+    #[arg(long, value_enum, default_value_t = ResolverKind::File)]
+    resolver: ResolverKind,
+    /// Registry index URL used when `--resolver registry` is selected.
+    #[arg(long, default_value = "https://registry.chronlang.org")]
+    registry: String,
+    /// Directory caching fetched registry modules.
+    #[arg(long, default_value = "./.chronlang/cache")]
+    cache_dir: PathBuf,
+    /// Lockfile pinning the resolved hash of each scoped import.
+    #[arg(long, default_value = "./chronlang.lock")]
+    lockfile: PathBuf,
+    /// Treat warnings as errors.
+    #[arg(long)]
+    werror: bool,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum ResolverKind {
+    /// Resolve every import from the local filesystem.
+    File,
+    /// Resolve local imports from disk, falling back to the remote registry
+    /// for scoped `@`-imports.
+    Registry,
+}
+
+fn main() -> ExitCode {
+    match Cli::parse().command {
+        Command::Check(args) => run_check(args),
+        Command::Build(args) => run_build(args),
+    }
+}
+
+fn run_check(args: Check) -> ExitCode {
+    let (result, sources) = match compile_file(&args.file, &args.common) {
+        Ok(compiled) => compiled,
+        Err(err) => return fail(err),
+    };
+
+    report(&result, &sources);
+    exit_code(&result, args.common.werror)
+}
+
+fn run_build(args: Build) -> ExitCode {
+    let (result, sources) = match compile_file(&args.file, &args.common) {
+        Ok(compiled) => compiled,
+        Err(err) => return fail(err),
+    };
+
+    report(&result, &sources);
+    if !ok(&result, args.common.werror) {
+        return exit_code(&result, args.common.werror);
+    }
+
+    let serialized = match serialize_project(&result) {
+        Ok(json) => json,
+        Err(err) => return fail(err),
+    };
+    let written = match &args.out {
+        Some(path) => fs::write(path, serialized),
+        None => std::io::stdout().write_all(serialized.as_bytes()),
+    };
+
+    match written {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => fail(format!("failed to write output: {err}")),
+    }
+}
+
+/// Serialize the compiled project as machine-readable JSON for downstream
+/// tools. Requires the `serde` feature that also backs the project cache.
+#[cfg(feature = "serde")]
+fn serialize_project(result: &CompilationResult) -> Result<String, String> {
+    serde_json::to_string_pretty(&result.project)
+        .map(|json| json + "\n")
+        .map_err(|err| format!("failed to serialize project: {err}"))
+}
+
+#[cfg(not(feature = "serde"))]
+fn serialize_project(_result: &CompilationResult) -> Result<String, String> {
+    Err("`build` requires the `serde` feature to serialize the project".into())
+}
+
+/// Read `file`, compile it with the selected resolver rooted at its directory,
+/// and capture the entry source for diagnostic rendering.
+fn compile_file(file: &Path, common: &Common) -> Result<(CompilationResult, Sources), String> {
+    let source = fs::read_to_string(file).map_err(|err| format!("could not read `{}`: {err}", file.display()))?;
+    let source_name = file.to_string_lossy().into_owned();
+
+    let mut sources = Sources::new();
+    sources.insert(&source_name, &source);
+
+    let base_path = file.parent().unwrap_or_else(|| Path::new("."));
+    let result = match common.resolver {
+        ResolverKind::File => {
+            let resolver = FileSystemResolver::new(base_path);
+            compile(&source, &source_name, &resolver)
+        }
+        ResolverKind::Registry => {
+            let registry = RegistryResolver::new(
+                &common.registry,
+                common.cache_dir.clone(),
+                Lockfile::new(common.lockfile.clone()),
+                HttpFetch,
+            );
+            let resolver = ChainResolver::new(FileSystemResolver::new(base_path), registry);
+            compile(&source, &source_name, &resolver)
+        }
+    };
+
+    Ok((result, sources))
+}
+
+fn report(result: &CompilationResult, sources: &Sources) {
+    let mut stderr = std::io::stderr();
+    let color = stderr.is_terminal();
+    let render = |diagnostic: &chronlang_engine::diagnostics::Diagnostic| {
+        if color {
+            diagnostic.render_ansi(sources)
+        } else {
+            diagnostic.render(sources)
+        }
+    };
+
+    for warning in &result.warnings {
+        let _ = writeln!(stderr, "{}", render(&warning.to_diagnostic(default_source(sources))));
+    }
+    for error in &result.errors {
+        let _ = writeln!(stderr, "{}", render(&error.to_diagnostic(default_source(sources))));
+    }
+}
+
+fn default_source(sources: &Sources) -> &str {
+    sources.entry_name().unwrap_or("")
+}
+
+fn ok(result: &CompilationResult, werror: bool) -> bool {
+    result.ok && !(werror && !result.warnings.is_empty())
+}
+
+fn exit_code(result: &CompilationResult, werror: bool) -> ExitCode {
+    if ok(result, werror) {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+fn fail(message: String) -> ExitCode {
+    eprintln!("error: {message}");
+    ExitCode::FAILURE
+}
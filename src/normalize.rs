@@ -0,0 +1,60 @@
+use unicode_normalization::UnicodeNormalization;
+
+/// The Unicode normalization form applied to every IPA segment so that
+/// precomposed and decomposed spellings of the same sound compare equal. The
+/// default is NFD — combining diacritics stay separable, which keeps
+/// feature-level matching predictable — but it can be overridden at compile
+/// time through the matching cargo features.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Form {
+    Nfc,
+    Nfd,
+    Nfkc,
+    Nfkd,
+}
+
+impl Default for Form {
+    fn default() -> Self {
+        if cfg!(feature = "nfc") {
+            Form::Nfc
+        } else if cfg!(feature = "nfkc") {
+            Form::Nfkc
+        } else if cfg!(feature = "nfkd") {
+            Form::Nfkd
+        } else {
+            Form::Nfd
+        }
+    }
+}
+
+impl Form {
+    /// Canonicalize a single segment to this form.
+    pub fn apply(self, segment: &str) -> String {
+        match self {
+            Form::Nfc => segment.nfc().collect(),
+            Form::Nfd => segment.nfd().collect(),
+            Form::Nfkc => segment.nfkc().collect(),
+            Form::Nfkd => segment.nfkd().collect(),
+        }
+    }
+
+    /// Canonicalize every segment in a pronunciation.
+    pub fn apply_all(self, segments: &[String]) -> Vec<String> {
+        segments.iter().map(|segment| self.apply(segment)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nfd_and_nfc_agree_on_a_precomposed_and_decomposed_segment() {
+        let precomposed = "é"; // U+00E9
+        let decomposed = "e\u{0301}"; // e + combining acute accent
+
+        assert_eq!(Form::Nfd.apply(precomposed), Form::Nfd.apply(decomposed));
+        assert_eq!(Form::Nfc.apply(decomposed), precomposed);
+    }
+}
@@ -1,17 +1,26 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+#[cfg(feature = "serde")]
+use std::fs;
+#[cfg(feature = "serde")]
+use std::io::{self, ErrorKind};
+#[cfg(feature = "serde")]
+use std::path::Path;
 
 use chronlang_parser::ast::{self, TraitMember};
 use crate::language::Language;
+use crate::normalize::Form;
 use crate::tag::Tag;
 use crate::word::Word;
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Location {
     pub source_name: String,
     pub span: ast::Span,
 }
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Symbol {
     pub name: String,
     pub loc: Location,
@@ -20,6 +29,7 @@ pub struct Symbol {
 }
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Entity {
     Language(Language),
     Word(Word),
@@ -48,6 +58,7 @@ pub enum Entity {
         default: bool,
         notation: Option<ast::Spanned<String>>,
     },
+    SoundChange(SoundChange),
 }
 
 #[derive(Debug, PartialEq)]
@@ -58,13 +69,15 @@ pub enum ImportError {
 }
 
 #[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Project {
     pub milestones: Vec<i64>,
     pub symbols: HashMap<String, Symbol>,
     pub languages: Vec<Language>,
     pub words: Vec<Word>,
     pub sound_changes: Vec<SoundChange>,
-    pub tags: Vec<Tag>
+    pub tags: Vec<Tag>,
+    pub normalization: Form,
 }
 
 impl Project {
@@ -76,9 +89,21 @@ impl Project {
             words: Vec::new(),
             sound_changes: Vec::new(),
             tags: Vec::new(),
+            normalization: Form::default(),
         }
     }
 
+    /// The normalization form applied to this project's segments. Queries must
+    /// be canonicalized to the same form before lookup.
+    pub fn normalization(&self) -> Form {
+        self.normalization
+    }
+
+    /// Canonicalize a query string to the project's normalization form.
+    pub fn normalize_query(&self, query: &str) -> String {
+        self.normalization.apply(query)
+    }
+
     pub fn add_symbol(&mut self, symbol: Symbol) -> Result<(), Symbol> {
         let id = symbol.name.clone();
         match self.symbols.get(&id) {
@@ -147,9 +172,180 @@ impl Project {
     pub fn import_all_from(&mut self, other: &Project) -> Result<(), Vec<ImportError>> {
         self.import(other.symbols.keys().map(|k| k.as_str()).collect::<Vec<_>>().as_slice(), other)
     }
+
+    /// The most specific symbol whose definition span covers `offset`, for
+    /// go-to-definition and hover. Narrowest enclosing span wins when phoneme
+    /// spans nest inside their class.
+    pub fn symbol_at(&self, offset: usize) -> Option<&Symbol> {
+        self.symbols
+            .values()
+            .filter(|symbol| symbol.loc.span.contains(&offset))
+            .min_by_key(|symbol| symbol.loc.span.end - symbol.loc.span.start)
+    }
+
+    /// Every symbol that lists `name` among its dependencies — the reverse of
+    /// the `dependencies` edges walked during import, e.g. all phonemes of a
+    /// class or all words and sound changes bound to a language.
+    pub fn references_to(&self, name: &str) -> Vec<&Symbol> {
+        self.symbols
+            .values()
+            .filter(|symbol| symbol.dependencies.iter().any(|dep| dep == name))
+            .collect()
+    }
+
+    /// Evolve a single word to `target_time` along `language`'s lineage. Every
+    /// sound change bound to a language in that chain whose milestone falls in
+    /// the half-open interval `[word origin, target_time)` is applied in time
+    /// order — stable on source order for ties — threading the intermediate
+    /// pronunciation through each rule. The returned word carries a tag at
+    /// `target_time`.
+    pub fn derive(&self, word: &Word, target_time: i64, language: &str) -> Word {
+        let lineage = crate::derive::lineage(self, language);
+        let origin = crate::derive::time_value(&word.tag.time);
+
+        let mut changes = self
+            .sound_changes
+            .iter()
+            .filter(|change| lineage.contains(&change.tag.language))
+            .filter(|change| {
+                let time = crate::derive::time_value(&change.tag.time);
+                origin <= time && time < target_time
+            })
+            .collect::<Vec<_>>();
+        changes.sort_by_key(|change| crate::derive::time_value(&change.tag.time));
+
+        let pronunciation = crate::derive::apply_sequence(self, word.pronunciation.clone(), &changes);
+        let tag = Tag {
+            language: language.to_string(),
+            lang_set_span: word.tag.lang_set_span.clone(),
+            time: ast::Time::Instant(target_time),
+            time_set_span: word.tag.time_set_span.clone(),
+        };
+
+        Word { pronunciation, tag, ..word.clone() }
+    }
+
+    /// Build a finite-state-transducer index over every word's pronunciation,
+    /// reusable across exact, prefix and subsequence lookups.
+    pub fn index_pronunciations(&self) -> crate::index::PronunciationIndex {
+        crate::index::PronunciationIndex::build(&self.words)
+    }
+
+    /// Every word whose pronunciation contains `pattern` as a subsequence, e.g.
+    /// all words with a /k/ somewhere before a /t/. The pattern segments are
+    /// canonicalized to the project's normalization form before matching.
+    pub fn find_by_pronunciation(&self, pattern: &[&str]) -> Vec<&Word> {
+        let normalized = pattern.iter().map(|seg| self.normalize_query(seg)).collect::<Vec<_>>();
+        let refs = normalized.iter().map(|seg| seg.as_str()).collect::<Vec<_>>();
+        self.index_pronunciations()
+            .contains(&refs)
+            .into_iter()
+            .map(|idx| &self.words[idx])
+            .collect()
+    }
+
+    /// Fuzzy-search word glosses, returning every word scoring at least the
+    /// match threshold paired with its score, best first. The query is canonicalized
+    /// to the project's normalization form before matching.
+    pub fn search_glosses(&self, query: &str) -> Vec<(f64, &Word)> {
+        let query = self.normalize_query(query);
+
+        let mut matches = self
+            .words
+            .iter()
+            .filter_map(|word| crate::search::fuzzy_match(&query, &word.gloss).map(|score| (score, word)))
+            .filter(|(score, _)| *score >= crate::search::THRESHOLD)
+            .collect::<Vec<_>>();
+
+        matches.sort_by(|(a, _), (b, _)| b.partial_cmp(a).unwrap_or(std::cmp::Ordering::Equal));
+        matches
+    }
+
+    /// Write the compiled project to `path` with a compact binary encoding, so
+    /// a later run can reload it instead of recompiling from source. Requires
+    /// the `serde` feature, which also enables `chronlang_parser`'s serde
+    /// support for the ast values embedded in the symbol table.
+    #[cfg(feature = "serde")]
+    pub fn save_cache(&self, path: &Path) -> io::Result<()> {
+        let bytes = bincode::serialize(self).map_err(|e| io::Error::new(ErrorKind::InvalidData, e))?;
+        fs::write(path, bytes)
+    }
+
+    /// Load a project previously written with [`Project::save_cache`].
+    #[cfg(feature = "serde")]
+    pub fn load_cache(path: &Path) -> io::Result<Self> {
+        let bytes = fs::read(path)?;
+        bincode::deserialize(&bytes).map_err(|e| io::Error::new(ErrorKind::InvalidData, e))
+    }
+
+    /// The names of every symbol that must be re-evaluated when the given
+    /// sources change: those defined in a changed source, plus everything that
+    /// transitively depends on them (walked through the reverse `dependencies`
+    /// edges, exactly as `import` walks them forwards). Everything else can be
+    /// reused from the cache.
+    pub fn stale_symbols(&self, changed_sources: &[&str]) -> HashSet<String> {
+        let mut stale = self
+            .symbols
+            .values()
+            .filter(|symbol| changed_sources.contains(&symbol.loc.source_name.as_str()))
+            .map(|symbol| symbol.name.clone())
+            .collect::<HashSet<_>>();
+
+        let mut frontier = stale.iter().cloned().collect::<Vec<_>>();
+        while let Some(name) = frontier.pop() {
+            for dependent in self.references_to(&name) {
+                if stale.insert(dependent.name.clone()) {
+                    frontier.push(dependent.name.clone());
+                }
+            }
+        }
+
+        stale
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn language_symbol(name: &str, source: &str, deps: Vec<String>) -> Symbol {
+        Symbol {
+            name: name.to_string(),
+            loc: Location { source_name: source.to_string(), span: 0..0 },
+            value: Entity::Language(Language::new(&(0..0, name.to_string()), &None, &None)),
+            dependencies: deps,
+        }
+    }
+
+    #[test]
+    fn stale_symbols_follows_reverse_dependencies() {
+        let mut project = Project::new();
+        project.add_symbol(language_symbol("A", "a.lang", vec![])).unwrap();
+        project.add_symbol(language_symbol("B", "b.lang", vec!["A".into()])).unwrap();
+        project.add_symbol(language_symbol("C", "c.lang", vec!["B".into()])).unwrap();
+
+        let stale = project.stale_symbols(&["a.lang"]);
+
+        assert_eq!(stale, HashSet::from(["A".into(), "B".into(), "C".into()]));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn cache_round_trips_through_disk() {
+        let mut project = Project::new();
+        project.add_symbol(language_symbol("A", "a.lang", vec![])).unwrap();
+
+        let path = std::env::temp_dir().join("chronlang_cache_test.bin");
+        project.save_cache(&path).unwrap();
+        let loaded = Project::load_cache(&path).unwrap();
+
+        assert_eq!(project, loaded);
+        let _ = fs::remove_file(&path);
+    }
 }
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SoundChange {
     pub source_name: String,
     pub source: ast::Spanned<ast::Source>,
@@ -1,3 +1,5 @@
+use std::cell::RefCell;
+use std::path::PathBuf;
 use std::{collections::HashMap, fs, path::Path};
 use std::fmt::Display;
 
@@ -5,6 +7,7 @@ use std::fmt::Display;
 pub enum ResolutionError {
     InvalidPathForResolver(String),
     PathNotFound(String),
+    PinMismatch(String, String, String),
 }
 
 impl Display for ResolutionError {
@@ -12,6 +15,8 @@ impl Display for ResolutionError {
         let str = match self {
             ResolutionError::InvalidPathForResolver(reason) => format!("{reason}. Try using a different resolver."),
             ResolutionError::PathNotFound(path) => format!("Failed to resolve path `{path}`."),
+            ResolutionError::PinMismatch(path, expected, actual) =>
+                format!("Path `{path}` is pinned to `{expected}` but the registry now serves `{actual}`. Update the lockfile to accept the new version."),
         };
         write!(f, "{}", str)
     }
@@ -24,12 +29,12 @@ pub trait Resolve {
 #[derive(Debug, PartialEq)]
 pub struct FileSystemResolver<'a> {
     base_path: &'a Path,
-    cache: HashMap<&'a str, String>,
+    cache: RefCell<HashMap<String, String>>,
 }
 
 impl FileSystemResolver<'_> {
     pub fn new(base_path: &Path) -> FileSystemResolver<'_> {
-        FileSystemResolver { base_path, cache: HashMap::new() }
+        FileSystemResolver { base_path, cache: RefCell::new(HashMap::new()) }
     }
 }
 
@@ -44,16 +49,19 @@ impl Resolve for FileSystemResolver<'_> {
 
         let mut dir = self.base_path.join(path.join("/"));
         dir.set_extension("lang");
-        let file_name = dir.to_str().unwrap();
+        let file_name = dir.to_str().unwrap().to_string();
 
-        if let Some(value) = self.cache.get(file_name) {
-            return Ok((value.clone(), file_name.to_string()));
+        if let Some(value) = self.cache.borrow().get(&file_name) {
+            return Ok((value.clone(), file_name));
         }
 
         let file = fs::read_to_string(dir.clone());
         match file {
-            Ok(contents) => Ok((contents, file_name.to_string())),
-            _ => Err(ResolutionError::PathNotFound(file_name.to_string())),
+            Ok(contents) => {
+                self.cache.borrow_mut().insert(file_name.clone(), contents.clone());
+                Ok((contents, file_name))
+            }
+            _ => Err(ResolutionError::PathNotFound(file_name)),
         }
     }
 }
@@ -80,10 +88,183 @@ impl Resolve for MockResolver {
 }
 
 
+/// The transport a `RegistryResolver` uses to pull a module from its package
+/// index. Abstracted so the HTTP client is swappable and the resolver is
+/// testable without a live network, mirroring how `MockResolver` stands in for
+/// the filesystem.
+pub trait Fetch {
+    fn fetch(&self, url: &str) -> Result<String, String>;
+}
+
+/// A `Fetch` backed by a blocking HTTP client, used by the CLI to pull modules
+/// from a live registry index over the network.
+pub struct HttpFetch;
+
+impl Fetch for HttpFetch {
+    fn fetch(&self, url: &str) -> Result<String, String> {
+        ureq::get(url)
+            .call()
+            .map_err(|err| err.to_string())?
+            .into_string()
+            .map_err(|err| err.to_string())
+    }
+}
+
+/// Records the content hash resolved for each scoped path so that later builds
+/// reuse exactly the same module version. Persisted next to the project as a
+/// line-oriented `path = hash` file.
+#[derive(Debug, PartialEq)]
+pub struct Lockfile {
+    path: PathBuf,
+}
+
+impl Lockfile {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    fn entries(&self) -> HashMap<String, String> {
+        fs::read_to_string(&self.path)
+            .unwrap_or_default()
+            .lines()
+            .filter_map(|line| line.split_once('='))
+            .map(|(key, hash)| (key.trim().to_string(), hash.trim().to_string()))
+            .collect()
+    }
+
+    fn get(&self, key: &str) -> Option<String> {
+        self.entries().get(key).cloned()
+    }
+
+    fn pin(&self, key: &str, hash: &str) {
+        let mut entries = self.entries();
+        entries.insert(key.to_string(), hash.to_string());
+        let mut keys = entries.keys().cloned().collect::<Vec<_>>();
+        keys.sort();
+        let body = keys
+            .iter()
+            .map(|key| format!("{key} = {}", entries[key]))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let _ = fs::write(&self.path, body + "\n");
+    }
+}
+
+/// Resolves `@scope/pkg/sub/module` imports against a package index, caching
+/// fetched sources on disk and pinning their content hash in a lockfile so
+/// resolution stays reproducible across runs.
+#[derive(Debug, PartialEq)]
+pub struct RegistryResolver<F: Fetch> {
+    index_url: String,
+    cache_dir: PathBuf,
+    lockfile: Lockfile,
+    fetch: F,
+}
+
+impl<F: Fetch> RegistryResolver<F> {
+    pub fn new(index_url: &str, cache_dir: PathBuf, lockfile: Lockfile, fetch: F) -> Self {
+        Self { index_url: index_url.trim_end_matches('/').to_string(), cache_dir, lockfile, fetch }
+    }
+
+    fn cache_path(&self, key: &str) -> PathBuf {
+        self.cache_dir.join(format!("{}.lang", key.replace(['@', '/'], "_")))
+    }
+}
+
+impl<F: Fetch> Resolve for RegistryResolver<F> {
+    fn resolve(&self, path: &[&str]) -> Result<(String, String), ResolutionError> {
+        match path.first() {
+            Some(segment) if segment.starts_with('@') => {}
+            _ => return Err(ResolutionError::InvalidPathForResolver(
+                "RegistryResolver only resolves scoped `@`-imports.".into(),
+            )),
+        }
+
+        let key = path.join("/");
+        let cache_path = self.cache_path(&key);
+        let pinned = self.lockfile.get(&key);
+
+        // Serve the cached copy whenever it still matches the pinned hash.
+        if let (Ok(cached), Some(pin)) = (fs::read_to_string(&cache_path), &pinned) {
+            if &content_hash(&cached) == pin {
+                return Ok((cached, key));
+            }
+        }
+
+        let source = self
+            .fetch
+            .fetch(&format!("{}/{key}", self.index_url))
+            .map_err(|_| ResolutionError::PathNotFound(key.clone()))?;
+        let hash = content_hash(&source);
+
+        // A lockfile entry freezes the version: a changed upstream is an error,
+        // not a silent re-pin, so pinning actually reproduces across runs.
+        if let Some(pin) = &pinned {
+            if pin != &hash {
+                return Err(ResolutionError::PinMismatch(key, pin.clone(), hash));
+            }
+        }
+
+        let _ = fs::create_dir_all(&self.cache_dir);
+        let _ = fs::write(&cache_path, &source);
+        if pinned.is_none() {
+            self.lockfile.pin(&key, &hash);
+        }
+
+        Ok((source, key))
+    }
+}
+
+/// Tries one resolver and falls back to another when the path is not one the
+/// first can handle, letting local and registry imports coexist in a project.
+#[derive(Debug, PartialEq)]
+pub struct ChainResolver<A: Resolve, B: Resolve> {
+    primary: A,
+    fallback: B,
+}
+
+impl<A: Resolve, B: Resolve> ChainResolver<A, B> {
+    pub fn new(primary: A, fallback: B) -> Self {
+        Self { primary, fallback }
+    }
+}
+
+impl<A: Resolve, B: Resolve> Resolve for ChainResolver<A, B> {
+    fn resolve(&self, path: &[&str]) -> Result<(String, String), ResolutionError> {
+        match self.primary.resolve(path) {
+            Ok(resolved) => Ok(resolved),
+            Err(_) => self.fallback.resolve(path),
+        }
+    }
+}
+
+/// A stable 64-bit FNV-1a content digest used to pin registry modules in the
+/// lockfile. Unlike the standard-library hashers, whose output is explicitly
+/// unspecified across toolchains, FNV-1a is fixed by its constants, so a pin
+/// keeps its meaning across Rust releases and machines.
+fn content_hash(contents: &str) -> String {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in contents.as_bytes() {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    format!("{hash:016x}")
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
 
+    struct StubFetch {
+        response: String,
+    }
+
+    impl Fetch for StubFetch {
+        fn fetch(&self, _url: &str) -> Result<String, String> {
+            Ok(self.response.clone())
+        }
+    }
+
     #[test]
     fn file_system_resolver_resolves_an_existing_path() {
         let base_path = Path::new("./example");
@@ -102,4 +283,86 @@ mod test {
             Err(ResolutionError::PathNotFound("./example/invalid.lang".into())),
         );
     }
+
+    #[test]
+    fn registry_resolver_fetches_caches_and_pins() {
+        let cache_dir = std::env::temp_dir().join("chronlang_registry_test");
+        let _ = fs::remove_dir_all(&cache_dir);
+        let lockfile = Lockfile::new(cache_dir.join("chronlang.lock"));
+        let resolver = RegistryResolver::new(
+            "https://registry.example/",
+            cache_dir.clone(),
+            lockfile,
+            StubFetch { response: "trait Voice { voiced, unvoiced }".into() },
+        );
+
+        let (source, source_name) = resolver.resolve(&["@core", "ipa"]).unwrap();
+
+        assert_eq!(source_name, "@core/ipa");
+        assert!(source.contains("Voice"));
+        assert!(cache_dir.join("_core_ipa.lang").exists());
+
+        let _ = fs::remove_dir_all(&cache_dir);
+    }
+
+    #[test]
+    fn registry_resolver_errors_when_upstream_drifts_from_the_pin() {
+        let cache_dir = std::env::temp_dir().join("chronlang_registry_pin");
+        let _ = fs::remove_dir_all(&cache_dir);
+        let lock_path = cache_dir.join("chronlang.lock");
+
+        // First resolve pins the hash of the original content.
+        let first = RegistryResolver::new(
+            "https://registry.example",
+            cache_dir.clone(),
+            Lockfile::new(lock_path.clone()),
+            StubFetch { response: "trait Voice { voiced }".into() },
+        );
+        first.resolve(&["@core", "ipa"]).unwrap();
+
+        // A later resolve that refetches different content must refuse to
+        // silently re-pin, so the lockfile genuinely freezes the version.
+        let _ = fs::remove_file(cache_dir.join("_core_ipa.lang"));
+        let second = RegistryResolver::new(
+            "https://registry.example",
+            cache_dir.clone(),
+            Lockfile::new(lock_path),
+            StubFetch { response: "trait Voice { voiced, unvoiced }".into() },
+        );
+
+        assert!(matches!(
+            second.resolve(&["@core", "ipa"]),
+            Err(ResolutionError::PinMismatch(_, _, _)),
+        ));
+
+        let _ = fs::remove_dir_all(&cache_dir);
+    }
+
+    #[test]
+    fn registry_resolver_rejects_unscoped_paths() {
+        let cache_dir = std::env::temp_dir().join("chronlang_registry_reject");
+        let resolver = RegistryResolver::new(
+            "https://registry.example",
+            cache_dir.clone(),
+            Lockfile::new(cache_dir.join("chronlang.lock")),
+            StubFetch { response: String::new() },
+        );
+
+        assert!(matches!(
+            resolver.resolve(&["local"]),
+            Err(ResolutionError::InvalidPathForResolver(_)),
+        ));
+    }
+
+    #[test]
+    fn chain_resolver_falls_back_on_scoped_paths() {
+        let primary = FileSystemResolver::new(Path::new("./example"));
+        let fallback = MockResolver::new(HashMap::from([
+            ("@core/ipa".into(), "trait Voice { voiced, unvoiced }".into()),
+        ]));
+        let resolver = ChainResolver::new(primary, fallback);
+
+        let (_, source_name) = resolver.resolve(&["@core", "ipa"]).unwrap();
+        assert_eq!(source_name, "@core/ipa");
+    }
 }
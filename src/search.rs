@@ -0,0 +1,106 @@
+use std::collections::HashMap;
+
+const MATCH_SCORE: f64 = 16.0;
+const BOUNDARY_BONUS: f64 = 10.0;
+const SEQUENTIAL_BONUS: f64 = 8.0;
+const GAP_PENALTY: f64 = 2.0;
+const MAX_LEADING_PENALTY: f64 = 9.0;
+
+/// The minimum score a gloss must reach to be considered a match.
+pub const THRESHOLD: f64 = 1.0;
+
+/// A 64-bit set of the distinct lowercased characters in `text`, used as a
+/// cheap reject filter: a gloss can only match a query if its bag is a
+/// superset of the query's.
+pub fn char_bag(text: &str) -> u64 {
+    text.chars()
+        .flat_map(|c| c.to_lowercase())
+        .fold(0u64, |bag, c| bag | (1 << (c as u32 % 64)))
+}
+
+/// Score `gloss` against `query` with a Sublime-style subsequence match,
+/// returning `None` when `query` is not a subsequence of `gloss`. Higher is
+/// better; matches at word boundaries and runs of consecutive characters are
+/// rewarded while leading noise and large gaps are penalized.
+pub fn fuzzy_match(query: &str, gloss: &str) -> Option<f64> {
+    if query.is_empty() {
+        return Some(0.0);
+    }
+    if char_bag(query) & char_bag(gloss) != char_bag(query) {
+        return None;
+    }
+
+    let query: Vec<char> = query.chars().flat_map(|c| c.to_lowercase()).collect();
+    let gloss: Vec<char> = gloss.chars().collect();
+    let mut memo: HashMap<(usize, usize), Option<f64>> = HashMap::new();
+
+    best_score(&query, 0, &gloss, 0, &mut memo)
+}
+
+/// Best achievable score for matching `query[qi..]` within `gloss[gi..]`,
+/// memoized on `(qi, gi)` to stay polynomial.
+fn best_score(query: &[char], qi: usize, gloss: &[char], gi: usize, memo: &mut HashMap<(usize, usize), Option<f64>>) -> Option<f64> {
+    if qi == query.len() {
+        return Some(0.0);
+    }
+    if gi >= gloss.len() {
+        return None;
+    }
+    if let Some(cached) = memo.get(&(qi, gi)) {
+        return *cached;
+    }
+
+    let mut best: Option<f64> = None;
+    for j in gi..gloss.len() {
+        if !gloss[j].to_lowercase().eq(std::iter::once(query[qi])) {
+            continue;
+        }
+
+        let Some(rest) = best_score(query, qi + 1, gloss, j + 1, memo) else { continue };
+
+        let gap = (j - gi) as f64;
+        let penalty = if gi == 0 {
+            (gap * GAP_PENALTY).min(MAX_LEADING_PENALTY)
+        } else {
+            gap * GAP_PENALTY
+        };
+        let bonus = boundary_bonus(gloss, j) + if j == gi && gi != 0 { SEQUENTIAL_BONUS } else { 0.0 };
+
+        let score = MATCH_SCORE + bonus - penalty + rest;
+        best = Some(best.map_or(score, |b: f64| b.max(score)));
+    }
+
+    memo.insert((qi, gi), best);
+    best
+}
+
+/// Whether position `j` in `gloss` begins a new word or segment — the start of
+/// the string, or following a separator or a lower→upper case change.
+fn boundary_bonus(gloss: &[char], j: usize) -> f64 {
+    let boundary = j == 0
+        || matches!(gloss[j - 1], ' ' | '-' | '_')
+        || (gloss[j - 1].is_lowercase() && gloss[j].is_uppercase());
+
+    if boundary {
+        BOUNDARY_BONUS
+    } else {
+        0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_rejects_non_subsequences() {
+        assert_eq!(fuzzy_match("xyz", "water"), None);
+    }
+
+    #[test]
+    fn it_prefers_boundary_matches() {
+        let spaced = fuzzy_match("rw", "river water").unwrap();
+        let scattered = fuzzy_match("rw", "narrow").unwrap();
+        assert!(spaced > scattered);
+    }
+}
@@ -0,0 +1,198 @@
+use crate::project::{Entity, Project};
+
+/// One element of a syllabification pattern: a word-edge anchor (`.`), a phoneme
+/// class (matching any of the class's members), or a literal phoneme.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Token {
+    Edge,
+    Class(String),
+    Phoneme(String),
+}
+
+/// A Knuth–Liang pattern: a sequence of tokens with a priority sitting in every
+/// gap, including before the first and after the last token. Odd priorities mark
+/// valid break points, even priorities suppress them.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Pattern {
+    pub tokens: Vec<Token>,
+    pub points: Vec<i32>,
+}
+
+impl Pattern {
+    /// Parse a pattern from its textual form, e.g. `. C 0 V 1 C .`. Whitespace
+    /// separates elements; integers are break priorities and every other
+    /// non-`.` token is a phoneme class.
+    pub fn parse(source: &str) -> Self {
+        let mut tokens = Vec::new();
+        let mut points = vec![0];
+
+        for element in source.split_whitespace() {
+            if let Ok(priority) = element.parse::<i32>() {
+                *points.last_mut().unwrap() = priority;
+            } else {
+                tokens.push(if element == "." { Token::Edge } else { Token::Class(element.to_string()) });
+                points.push(0);
+            }
+        }
+
+        Self { tokens, points }
+    }
+}
+
+/// Syllabifies pronunciations by overlaying Knuth–Liang patterns. Patterns are
+/// held in a trie keyed on their tokens so that every alignment is matched in a
+/// single walk per start position.
+#[derive(Debug, Default)]
+pub struct Syllabifier {
+    root: Node,
+}
+
+#[derive(Debug, Default)]
+struct Node {
+    children: Vec<(Token, Node)>,
+    points: Option<Vec<i32>>,
+}
+
+impl Syllabifier {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Build a syllabifier from a set of patterns, e.g. the patterns for a
+    /// single language.
+    pub fn from_patterns(patterns: impl IntoIterator<Item = Pattern>) -> Self {
+        let mut syllabifier = Self::new();
+        for pattern in patterns {
+            syllabifier.insert(pattern);
+        }
+        syllabifier
+    }
+
+    fn insert(&mut self, pattern: Pattern) {
+        let mut node = &mut self.root;
+        for token in pattern.tokens {
+            let position = node.children.iter().position(|(candidate, _)| *candidate == token);
+            let position = match position {
+                Some(position) => position,
+                None => {
+                    node.children.push((token, Node::default()));
+                    node.children.len() - 1
+                }
+            };
+            node = &mut node.children[position].1;
+        }
+        node.points = Some(pattern.points);
+    }
+
+    /// Split `segments` into syllables. Classes are resolved against `project`,
+    /// and a break is placed at every internal position whose winning priority
+    /// is odd.
+    pub fn syllabify(&self, project: &Project, segments: &[String]) -> Vec<Vec<String>> {
+        let n = segments.len();
+        if n == 0 {
+            return Vec::new();
+        }
+
+        let mut padded = vec![None];
+        padded.extend(segments.iter().map(|segment| Some(segment.as_str())));
+        padded.push(None);
+
+        // One priority per inter-segment position, 0..=n; the maximum
+        // contributed by any pattern at any alignment wins.
+        let mut levels = vec![0i32; n + 1];
+        for start in 0..padded.len() {
+            self.walk(project, &self.root, &padded, start, 0, &mut levels);
+        }
+
+        let mut syllables = Vec::new();
+        let mut current = Vec::new();
+        for (i, segment) in segments.iter().enumerate() {
+            current.push(segment.clone());
+            let boundary = i + 1;
+            if boundary < n && levels[boundary] % 2 != 0 {
+                syllables.push(std::mem::take(&mut current));
+            }
+        }
+        if !current.is_empty() {
+            syllables.push(current);
+        }
+        syllables
+    }
+
+    fn walk(&self, project: &Project, node: &Node, padded: &[Option<&str>], start: usize, depth: usize, levels: &mut [i32]) {
+        if let Some(points) = &node.points {
+            for (k, &priority) in points.iter().enumerate() {
+                if let Some(boundary) = (start + k).checked_sub(1) {
+                    if boundary < levels.len() {
+                        levels[boundary] = levels[boundary].max(priority);
+                    }
+                }
+            }
+        }
+
+        let index = start + depth;
+        let Some(segment) = padded.get(index) else { return };
+        for (token, child) in &node.children {
+            if matches(project, token, *segment) {
+                self.walk(project, child, padded, start, depth + 1, levels);
+            }
+        }
+    }
+}
+
+fn matches(project: &Project, token: &Token, segment: Option<&str>) -> bool {
+    match token {
+        Token::Edge => segment.is_none(),
+        Token::Phoneme(phoneme) => segment == Some(phoneme.as_str()),
+        Token::Class(class) => segment.map(|segment| class_contains(project, class, segment)).unwrap_or(false),
+    }
+}
+
+/// Whether `segment` is a member of the named class, either listed in the
+/// class's phonemes or tagged with it as its class.
+fn class_contains(project: &Project, class: &str, segment: &str) -> bool {
+    match project.symbols.get(class).map(|symbol| &symbol.value) {
+        Some(Entity::Class { phonemes, .. }) => phonemes.iter().any(|phoneme| phoneme == segment),
+        _ => false,
+    }
+    || matches!(
+        project.symbols.get(segment).map(|symbol| &symbol.value),
+        Some(Entity::Phoneme { class: (_, phoneme_class), .. }) if phoneme_class == class
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::project::{Location, Symbol};
+
+    use super::*;
+
+    fn class(label: &str, phonemes: &[&str]) -> Symbol {
+        Symbol {
+            name: label.to_string(),
+            loc: Location { source_name: "demo".into(), span: 0..0 },
+            value: Entity::Class {
+                label: (0..0, label.to_string()),
+                encodes: Vec::new(),
+                annotates: Vec::new(),
+                phonemes: phonemes.iter().map(|p| p.to_string()).collect(),
+            },
+            dependencies: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn it_breaks_on_odd_priorities() {
+        let mut project = Project::new();
+        project.add_symbol(class("C", &["t"])).unwrap();
+        project.add_symbol(class("V", &["a"])).unwrap();
+
+        let syllabifier = Syllabifier::from_patterns([Pattern::parse(". C V 1 C V .")]);
+        let segments = vec!["t".into(), "a".into(), "t".into(), "a".into()];
+
+        assert_eq!(
+            syllabifier.syllabify(&project, &segments),
+            vec![vec!["t".to_string(), "a".into()], vec!["t".into(), "a".into()]],
+        );
+    }
+}
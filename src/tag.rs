@@ -1,6 +1,7 @@
 use chronlang_parser::ast;
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Tag {
     pub language: String,
     pub lang_set_span: ast::Span,
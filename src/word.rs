@@ -1,7 +1,9 @@
 use chronlang_parser::ast::{Span, Spanned};
+use crate::normalize::Form;
 use crate::tag::Tag;
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Word {
     pub gloss: String,
     pub gloss_span: Span,
@@ -12,11 +14,11 @@ pub struct Word {
 }
 
 impl Word {
-    pub fn new(gloss: &Spanned<String>, prn: &Spanned<Vec<String>>, definitions: &Vec<Definition>, tag: &Tag) -> Self {
+    pub fn new(gloss: &Spanned<String>, prn: &Spanned<Vec<String>>, definitions: &Vec<Definition>, tag: &Tag, form: Form) -> Self {
         Self {
             gloss: gloss.1.clone(),
             gloss_span: gloss.0.clone(),
-            pronunciation: prn.1.clone(),
+            pronunciation: form.apply_all(&prn.1),
             pronunciation_span: prn.0.clone(),
             definitions: definitions.clone(),
             tag: tag.clone(),
@@ -25,6 +27,7 @@ impl Word {
 }
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Definition {
     pub pos: Option<String>,
     pub pos_span: Option<Span>,